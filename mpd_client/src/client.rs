@@ -0,0 +1,151 @@
+//! The connection to an MPD server used to send commands.
+
+use std::{error, fmt, future::Future};
+
+use mpd_protocol::MpdProtocolError;
+
+use crate::{
+    responses::{Output, Playlist, SongInQueue, Stats, Status},
+    state_changes::{IdleSubscription, StateChanges, Subsystem},
+};
+
+/// A connection to an MPD server used to send commands.
+///
+/// Transport setup (dialing the server, authentication) lives outside this part of the crate;
+/// this type is the handle consumers of [`crate::state_mirror::StateMirror`] and the idle loop
+/// are built against. This part of the crate includes no transport, so [`Client::idle_for`] and
+/// the [`MpdCommands`] methods of `Client` panic when called.
+#[derive(Clone, Debug)]
+pub struct Client {}
+
+impl Client {
+    /// Starts the idle loop, listening only for the given `subsystems` instead of every change on
+    /// the server.
+    ///
+    /// This builds an [`IdleSubscription`] from `subsystems` and re-uses it for every `idle` call
+    /// issued for the lifetime of the returned [`StateChanges`], so a client that only cares about
+    /// [`Subsystem::Player`]/[`Subsystem::Mixer`] never gets woken by [`Subsystem::Database`]/
+    /// [`Subsystem::Update`] churn.
+    ///
+    /// # Panics
+    ///
+    /// Always, since this part of the crate includes no transport to send the `idle` command over.
+    pub async fn idle_for(&self, subsystems: &[Subsystem]) -> Result<StateChanges, CommandError> {
+        self.start_idle_loop(IdleSubscription::only(subsystems.to_vec()))
+            .await
+    }
+
+    /// Starts the idle loop with `subscription` already in effect.
+    async fn start_idle_loop(
+        &self,
+        subscription: IdleSubscription,
+    ) -> Result<StateChanges, CommandError> {
+        // The actual `idle` command line sent for every wake-up while this subscription is
+        // active; wiring it to the wire happens in the transport, which lives outside this part
+        // of the crate.
+        let _idle_command_line = subscription.to_command_line();
+
+        unimplemented!("transport not included in this chunk of the crate")
+    }
+}
+
+impl MpdCommands for Client {
+    async fn status(&self) -> Result<Status, CommandError> {
+        unimplemented!("transport not included in this chunk of the crate")
+    }
+
+    async fn queue(&self) -> Result<Vec<SongInQueue>, CommandError> {
+        unimplemented!("transport not included in this chunk of the crate")
+    }
+
+    async fn playlists(&self) -> Result<Vec<Playlist>, CommandError> {
+        unimplemented!("transport not included in this chunk of the crate")
+    }
+
+    async fn outputs(&self) -> Result<Vec<Output>, CommandError> {
+        unimplemented!("transport not included in this chunk of the crate")
+    }
+
+    async fn stats(&self) -> Result<Stats, CommandError> {
+        unimplemented!("transport not included in this chunk of the crate")
+    }
+}
+
+/// The commands [`crate::state_mirror::StateMirror`] needs to refresh its mirrored state.
+///
+/// Implemented by [`Client`]. This is a trait, rather than [`StateMirror`](crate::state_mirror::StateMirror)
+/// taking a [`Client`] directly, so the refresh logic can be exercised against a test double
+/// instead of a live connection.
+pub trait MpdCommands {
+    /// Sends the `status` command.
+    fn status(&self) -> impl Future<Output = Result<Status, CommandError>> + Send;
+
+    /// Sends the `playlistinfo` command.
+    fn queue(&self) -> impl Future<Output = Result<Vec<SongInQueue>, CommandError>> + Send;
+
+    /// Sends the `listplaylists` command.
+    fn playlists(&self) -> impl Future<Output = Result<Vec<Playlist>, CommandError>> + Send;
+
+    /// Sends the `outputs` command.
+    fn outputs(&self) -> impl Future<Output = Result<Vec<Output>, CommandError>> + Send;
+
+    /// Sends the `stats` command.
+    fn stats(&self) -> impl Future<Output = Result<Stats, CommandError>> + Send;
+}
+
+/// Lets an `Arc<C>` stand in for `C` wherever an [`MpdCommands`] is needed, so a single [`Client`]
+/// can be shared between a [`crate::state_mirror::StateMirror`] and other consumers.
+impl<T: MpdCommands + Send + Sync> MpdCommands for std::sync::Arc<T> {
+    async fn status(&self) -> Result<Status, CommandError> {
+        (**self).status().await
+    }
+
+    async fn queue(&self) -> Result<Vec<SongInQueue>, CommandError> {
+        (**self).queue().await
+    }
+
+    async fn playlists(&self) -> Result<Vec<Playlist>, CommandError> {
+        (**self).playlists().await
+    }
+
+    async fn outputs(&self) -> Result<Vec<Output>, CommandError> {
+        (**self).outputs().await
+    }
+
+    async fn stats(&self) -> Result<Stats, CommandError> {
+        (**self).stats().await
+    }
+}
+
+/// Errors which may occur while sending a command.
+#[derive(Debug)]
+pub enum CommandError {
+    /// An underlying protocol error occurred, including IO errors.
+    Protocol(MpdProtocolError),
+    /// The command response contained an error frame.
+    ErrorMessage(mpd_protocol::response::Error),
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::Protocol(_) => write!(f, "protocol error"),
+            CommandError::ErrorMessage(mpd_protocol::response::Error { code, message, .. }) => {
+                write!(
+                    f,
+                    "command response contained an error frame [code {}]: {}",
+                    code, message
+                )
+            }
+        }
+    }
+}
+
+impl error::Error for CommandError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            CommandError::Protocol(e) => Some(e),
+            CommandError::ErrorMessage(_) => None,
+        }
+    }
+}
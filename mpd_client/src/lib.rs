@@ -0,0 +1,6 @@
+//! Asynchronous client library for the Music Player Daemon.
+
+pub mod client;
+pub mod responses;
+pub mod state_changes;
+pub mod state_mirror;
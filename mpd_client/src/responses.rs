@@ -0,0 +1,62 @@
+//! Parsed responses to MPD commands.
+
+use std::time::Duration;
+
+/// Playback state, part of [`Status`].
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PlayState {
+    #[default]
+    Stopped,
+    Playing,
+    Paused,
+}
+
+/// The current player status, as returned by the `status` command.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Status {
+    /// Current playback state.
+    pub state: PlayState,
+    /// Current volume, `0..=100`, if known.
+    pub volume: Option<u8>,
+    /// Position of the current song in the queue, if any.
+    pub current_song: Option<u32>,
+    /// Elapsed playback time of the current song, if any.
+    pub elapsed: Option<Duration>,
+}
+
+/// A song entry in the current queue, as returned by the `playlistinfo` command.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SongInQueue {
+    /// Position in the queue.
+    pub position: u32,
+    /// The song's URI.
+    pub file: String,
+}
+
+/// A stored playlist, as returned by the `listplaylists` command.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Playlist {
+    /// Playlist name.
+    pub name: String,
+}
+
+/// A configured audio output, as returned by the `outputs` command.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Output {
+    /// Output ID.
+    pub id: u32,
+    /// Output name.
+    pub name: String,
+    /// Whether the output is currently enabled.
+    pub enabled: bool,
+}
+
+/// Database statistics, as returned by the `stats` command.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Stats {
+    /// Number of songs in the database.
+    pub songs: u32,
+    /// Time MPD has been running.
+    pub uptime: Duration,
+}
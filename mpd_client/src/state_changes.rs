@@ -1,12 +1,19 @@
 //! Tools for handling state-change events emitted by MPD.
 
 use std::{
+    collections::{HashMap, HashSet},
+    future::{poll_fn, Future},
     pin::Pin,
     task::{Context, Poll},
+    time::Duration,
 };
 
 use futures_core::stream::Stream;
-use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::{
+    sync::mpsc::{self, UnboundedReceiver},
+    task::JoinHandle,
+    time,
+};
 
 /// Stream of state change events.
 ///
@@ -19,6 +26,7 @@ use tokio::sync::mpsc::UnboundedReceiver;
 #[derive(Debug)]
 pub struct StateChanges {
     pub(crate) rx: UnboundedReceiver<Result<Subsystem, StateChangeError>>,
+    pub(crate) subscription: IdleSubscription,
 }
 
 impl Stream for StateChanges {
@@ -30,6 +38,459 @@ impl Stream for StateChanges {
     }
 }
 
+/// Which subsystems a [`StateChanges`] listener receives notifications for.
+///
+/// Passed to the client when starting the idle loop (see [`crate::client::Client::idle_for`]),
+/// and serialized into the `IDLE` command's optional subsystem list so MPD only wakes
+/// the connection for the subsystems that were asked for, instead of every change on the server.
+///
+/// The subscription is re-used for every subsequent `idle` call issued while listening, so the
+/// filter stays in effect for the lifetime of the [`StateChanges`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IdleSubscription(Option<Vec<Subsystem>>);
+
+impl IdleSubscription {
+    /// Subscribes to every subsystem.
+    ///
+    /// This is the default, and matches calling the bare `idle` command with no subsystem list.
+    pub fn all() -> Self {
+        Self(None)
+    }
+
+    /// Subscribes only to the given `subsystems`.
+    pub fn only(subsystems: impl Into<Vec<Subsystem>>) -> Self {
+        Self(Some(subsystems.into()))
+    }
+
+    /// Returns the field values to pass to the `IDLE` command, or `None` if every subsystem
+    /// should be listened for (in which case no subsystem list is sent at all).
+    pub(crate) fn command_args(&self) -> Option<impl Iterator<Item = &str>> {
+        self.0
+            .as_deref()
+            .map(|subsystems| subsystems.iter().map(Subsystem::as_str))
+    }
+
+    /// Builds the literal `IDLE` command line for this subscription, e.g. `"idle"` for
+    /// [`IdleSubscription::all`] or `"idle player mixer"` for a subscription filtered to
+    /// [`Subsystem::Player`] and [`Subsystem::Mixer`].
+    ///
+    /// [`crate::client::Client::idle_for`] builds this line from its subscription, and this is the
+    /// actual consumer of [`IdleSubscription::command_args`].
+    pub(crate) fn to_command_line(&self) -> String {
+        let mut line = String::from("idle");
+
+        if let Some(args) = self.command_args() {
+            for arg in args {
+                line.push(' ');
+                line.push_str(arg);
+            }
+        }
+
+        line
+    }
+}
+
+#[cfg(test)]
+mod idle_subscription_tests {
+    use super::*;
+
+    #[test]
+    fn all_sends_bare_idle() {
+        assert_eq!(IdleSubscription::all().to_command_line(), "idle");
+        assert_eq!(IdleSubscription::default().to_command_line(), "idle");
+    }
+
+    #[test]
+    fn only_sends_filtered_subsystem_list() {
+        let subscription = IdleSubscription::only([Subsystem::Player, Subsystem::Mixer]);
+
+        assert_eq!(subscription.to_command_line(), "idle player mixer");
+    }
+}
+
+impl StateChanges {
+    /// Returns the [`IdleSubscription`] currently in effect for this listener.
+    pub fn subscription(&self) -> &IdleSubscription {
+        &self.subscription
+    }
+}
+
+impl StateChanges {
+    /// Returns a stream which only yields the given `subsystems`, silently discarding events for
+    /// any other subsystem as well as errors.
+    ///
+    /// This is useful when only one part of an application cares about a given subsystem, e.g. a
+    /// volume widget that only wants [`Subsystem::Mixer`] events without being woken for queue or
+    /// database changes.
+    ///
+    /// The synthetic resync event produced by [`StateChanges::resilient`] (see
+    /// [`Subsystem::is_resync`]) is always let through regardless of `subsystems`, since it isn't
+    /// a real subsystem to filter on and consumers mirroring state need it to know they may have
+    /// missed changes while disconnected.
+    pub fn split(self, subsystems: &[Subsystem]) -> Split {
+        Split {
+            inner: self,
+            wanted: subsystems.iter().cloned().collect(),
+        }
+    }
+}
+
+/// Stream adapter returned by [`StateChanges::split`], which only yields the configured
+/// [`Subsystem`]s (plus the resync event, see [`StateChanges::split`]).
+#[derive(Debug)]
+pub struct Split {
+    inner: StateChanges,
+    wanted: HashSet<Subsystem>,
+}
+
+impl Stream for Split {
+    type Item = Subsystem;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match Pin::new(&mut self.inner).poll_next(cx) {
+                Poll::Ready(Some(Ok(subsystem)))
+                    if subsystem.is_resync() || self.wanted.contains(&subsystem) =>
+                {
+                    return Poll::Ready(Some(subsystem))
+                }
+                Poll::Ready(Some(_)) => continue,
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod split_tests {
+    use super::*;
+
+    fn changes_from(rx: UnboundedReceiver<Result<Subsystem, StateChangeError>>) -> StateChanges {
+        StateChanges {
+            rx,
+            subscription: IdleSubscription::all(),
+        }
+    }
+
+    #[tokio::test]
+    async fn only_yields_wanted_subsystems() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        tx.send(Ok(Subsystem::Mixer)).unwrap();
+        tx.send(Ok(Subsystem::Queue)).unwrap();
+        tx.send(Ok(Subsystem::Player)).unwrap();
+
+        let mut split = changes_from(rx).split(&[Subsystem::Player]);
+
+        let event = poll_fn(|cx| Pin::new(&mut split).poll_next(cx)).await;
+        assert_eq!(event, Some(Subsystem::Player));
+
+        drop(tx);
+        let event = poll_fn(|cx| Pin::new(&mut split).poll_next(cx)).await;
+        assert_eq!(event, None);
+    }
+
+    #[tokio::test]
+    async fn resync_event_passes_through_regardless_of_wanted_subsystems() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        tx.send(Ok(Subsystem::Other(Box::from("resync")))).unwrap();
+        drop(tx);
+
+        let mut split = changes_from(rx).split(&[Subsystem::Player]);
+
+        let event = poll_fn(|cx| Pin::new(&mut split).poll_next(cx)).await;
+        assert!(matches!(event, Some(subsystem) if subsystem.is_resync()));
+    }
+}
+
+impl StateChanges {
+    /// Returns a stream which coalesces every subsystem reported by a single idle wake-up --
+    /// i.e. everything immediately available without waiting for another wake-up -- into one
+    /// [`SubsystemSet`], instead of yielding them one at a time.
+    ///
+    /// A single `idle` response (and especially draining a `noidle`) can report several changed
+    /// subsystems at once. Batching them lets a consumer react once per wake-up, e.g. refreshing
+    /// `status` and the queue together, instead of redundantly refetching once per subsystem.
+    pub fn batched(self) -> Batched {
+        Batched {
+            inner: self,
+            pending_error: None,
+        }
+    }
+}
+
+/// Stream adapter returned by [`StateChanges::batched`].
+#[derive(Debug)]
+pub struct Batched {
+    inner: StateChanges,
+    /// An error hit while draining a batch, yielded on the poll after that batch.
+    pending_error: Option<StateChangeError>,
+}
+
+impl Stream for Batched {
+    type Item = Result<SubsystemSet, StateChangeError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(e) = self.pending_error.take() {
+            return Poll::Ready(Some(Err(e)));
+        }
+
+        let first = match Pin::new(&mut self.inner).poll_next(cx) {
+            Poll::Ready(Some(Ok(subsystem))) => subsystem,
+            Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e))),
+            Poll::Ready(None) => return Poll::Ready(None),
+            Poll::Pending => return Poll::Pending,
+        };
+
+        let mut set = HashSet::new();
+        set.insert(first);
+
+        // Drain anything else that's immediately available, without blocking for another
+        // wake-up.
+        loop {
+            match Pin::new(&mut self.inner).poll_next(cx) {
+                Poll::Ready(Some(Ok(subsystem))) => {
+                    set.insert(subsystem);
+                }
+                Poll::Ready(Some(Err(e))) => {
+                    self.pending_error = Some(e);
+                    break;
+                }
+                Poll::Ready(None) | Poll::Pending => break,
+            }
+        }
+
+        Poll::Ready(Some(Ok(SubsystemSet(set))))
+    }
+}
+
+/// A deduplicated set of [`Subsystem`]s, as produced by [`StateChanges::batched`] from a single
+/// idle wake-up.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SubsystemSet(HashSet<Subsystem>);
+
+impl SubsystemSet {
+    /// Returns whether `subsystem` is present in this set.
+    pub fn contains(&self, subsystem: &Subsystem) -> bool {
+        self.0.contains(subsystem)
+    }
+
+    /// Returns an iterator over the subsystems in this set.
+    pub fn iter(&self) -> impl Iterator<Item = &Subsystem> {
+        self.0.iter()
+    }
+
+    /// Returns the number of distinct subsystems in this set.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if this set contains no subsystems.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl IntoIterator for SubsystemSet {
+    type Item = Subsystem;
+    type IntoIter = std::collections::hash_set::IntoIter<Subsystem>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod batched_tests {
+    use super::*;
+
+    fn changes_from(rx: UnboundedReceiver<Result<Subsystem, StateChangeError>>) -> StateChanges {
+        StateChanges {
+            rx,
+            subscription: IdleSubscription::all(),
+        }
+    }
+
+    #[tokio::test]
+    async fn coalesces_everything_available_without_blocking() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        tx.send(Ok(Subsystem::Player)).unwrap();
+        tx.send(Ok(Subsystem::Mixer)).unwrap();
+        tx.send(Ok(Subsystem::Player)).unwrap(); // duplicate, should be deduplicated
+
+        let mut batched = changes_from(rx).batched();
+        let set = poll_fn(|cx| Pin::new(&mut batched).poll_next(cx))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&Subsystem::Player));
+        assert!(set.contains(&Subsystem::Mixer));
+
+        drop(tx);
+    }
+
+    #[tokio::test]
+    async fn stream_end_after_a_partial_batch_is_reported_on_the_next_poll() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        tx.send(Ok(Subsystem::Player)).unwrap();
+        drop(tx);
+
+        let mut batched = changes_from(rx).batched();
+
+        let set = poll_fn(|cx| Pin::new(&mut batched).poll_next(cx))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(set.len(), 1);
+
+        let ended = poll_fn(|cx| Pin::new(&mut batched).poll_next(cx)).await;
+        assert!(ended.is_none());
+    }
+}
+
+/// A handler registered with a [`StateChangeEmitter`].
+type Handler = Box<dyn FnMut() + Send>;
+
+/// An emitter-style registry of per-subsystem callbacks, mirroring the "listen for this one
+/// subsystem" idiom common in other MPD client libraries.
+///
+/// Register handlers with [`StateChangeEmitter::on`], then hand a [`StateChanges`] stream to
+/// [`StateChangeEmitter::listen`] to start dispatching events to them in the background:
+///
+/// ```no_run
+/// # async fn doc(changes: mpd_client::state_changes::StateChanges) {
+/// use mpd_client::state_changes::{StateChangeEmitter, Subsystem};
+///
+/// let mut emitter = StateChangeEmitter::new();
+/// emitter.on(Subsystem::Mixer, || println!("volume changed"));
+/// emitter.on(Subsystem::Queue, || println!("queue changed"));
+///
+/// emitter.listen(changes);
+/// # }
+/// ```
+#[derive(Default)]
+pub struct StateChangeEmitter {
+    handlers: HashMap<Subsystem, Vec<Handler>>,
+}
+
+impl StateChangeEmitter {
+    /// Creates an empty emitter with no registered handlers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `handler` to be called whenever an event for `subsystem` is emitted.
+    ///
+    /// Registering a handler for [`Subsystem::Other`] acts as a catch-all, matching any raw
+    /// subsystem name reported by MPD that doesn't have a dedicated variant, regardless of the
+    /// name carried in the variant passed here.
+    pub fn on(&mut self, subsystem: Subsystem, handler: impl FnMut() + Send + 'static) -> &mut Self {
+        self.handlers
+            .entry(Self::bucket(&subsystem))
+            .or_default()
+            .push(Box::new(handler));
+
+        self
+    }
+
+    /// Consumes `changes`, dispatching every event it yields to the registered handlers until the
+    /// stream ends, in a background task.
+    ///
+    /// Errors yielded by `changes` are ignored; only successfully decoded events are dispatched.
+    pub fn listen(mut self, mut changes: StateChanges) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                match poll_fn(|cx| Pin::new(&mut changes).poll_next(cx)).await {
+                    Some(Ok(subsystem)) => self.dispatch(&subsystem),
+                    Some(Err(_)) => continue,
+                    None => break,
+                }
+            }
+        })
+    }
+
+    fn dispatch(&mut self, subsystem: &Subsystem) {
+        if let Some(handlers) = self.handlers.get_mut(&Self::bucket(subsystem)) {
+            for handler in handlers {
+                handler();
+            }
+        }
+    }
+
+    /// Normalizes `subsystem` to a lookup key, collapsing all [`Subsystem::Other`] values into a
+    /// single catch-all bucket.
+    fn bucket(subsystem: &Subsystem) -> Subsystem {
+        match subsystem {
+            Subsystem::Other(_) => Subsystem::Other(Box::from("")),
+            other => other.clone(),
+        }
+    }
+}
+
+impl std::fmt::Debug for StateChangeEmitter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StateChangeEmitter")
+            .field("subsystems", &self.handlers.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod state_change_emitter_tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use super::*;
+
+    #[test]
+    fn dispatches_to_matching_subsystem_only() {
+        let mixer_calls = Arc::new(AtomicUsize::new(0));
+        let queue_calls = Arc::new(AtomicUsize::new(0));
+
+        let mut emitter = StateChangeEmitter::new();
+
+        {
+            let mixer_calls = Arc::clone(&mixer_calls);
+            emitter.on(Subsystem::Mixer, move || {
+                mixer_calls.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+        {
+            let queue_calls = Arc::clone(&queue_calls);
+            emitter.on(Subsystem::Queue, move || {
+                queue_calls.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+
+        emitter.dispatch(&Subsystem::Mixer);
+
+        assert_eq!(mixer_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(queue_calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn other_handlers_act_as_catch_all() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut emitter = StateChangeEmitter::new();
+
+        {
+            let calls = Arc::clone(&calls);
+            emitter.on(Subsystem::Other(Box::from("")), move || {
+                calls.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+
+        emitter.dispatch(&Subsystem::Other(Box::from("partition")));
+        emitter.dispatch(&Subsystem::Other(Box::from("sticker_removed")));
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
+
 /// Subsystems of MPD which can receive state change notifications.
 ///
 /// Derived from [the documentation](https://www.musicpd.org/doc/html/protocol.html#command-idle),
@@ -100,7 +561,21 @@ impl Subsystem {
             Subsystem::Other(r) => r,
         }
     }
+
+    /// Returns whether this is the synthetic resync event emitted by [`StateChanges::resilient`]
+    /// right after a successful reconnect, signalling that consumers should re-fetch any state
+    /// they're mirroring since changes may have been missed while disconnected.
+    ///
+    /// This is `pub` rather than crate-private so that code built on top of [`StateChanges`]
+    /// outside this crate can recognize and special-case the resync event, the way [`Split`] lets
+    /// it through a subsystem filter that would otherwise discard it.
+    pub fn is_resync(&self) -> bool {
+        matches!(self, Subsystem::Other(name) if &**name == RESYNC_SUBSYSTEM)
+    }
 }
+
+/// Raw name used for the synthetic resync event produced by [`resync_event`].
+const RESYNC_SUBSYSTEM: &str = "resync";
 use std::{error, fmt};
 
 use mpd_protocol::{response::Error, MpdProtocolError};
@@ -149,3 +624,183 @@ impl From<MpdProtocolError> for StateChangeError {
         StateChangeError::Protocol(e)
     }
 }
+
+/// Policy controlling the automatic reconnection performed by [`StateChanges::resilient`].
+#[derive(Clone, Debug)]
+pub struct ReconnectPolicy {
+    /// Delay before the first reconnect attempt.
+    pub initial_backoff: Duration,
+    /// Upper bound the exponentially growing backoff between attempts is capped at.
+    pub max_backoff: Duration,
+    /// Maximum number of consecutive reconnect attempts before giving up and ending the stream,
+    /// or `None` to retry forever.
+    pub max_retries: Option<u32>,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            max_retries: None,
+        }
+    }
+}
+
+impl StateChanges {
+    /// Wraps this listener in a supervising task which transparently reconnects on connection
+    /// loss, instead of ending the stream.
+    ///
+    /// On a retryable disconnect -- the stream ending (`None`), or an IO error from the
+    /// underlying connection ([`StateChangeError::Protocol`] wrapping [`MpdProtocolError::Io`])
+    /// -- `reconnect` is called, with backoff governed by `policy`, to re-establish the
+    /// connection and re-issue `idle` with the [`IdleSubscription`] that was in effect before the
+    /// disconnect. Once reconnected, a synthetic resync event is emitted before normal events
+    /// resume, so consumers (e.g. a state mirror) know to re-fetch whatever they were tracking,
+    /// since changes may have been missed while disconnected.
+    ///
+    /// A state change message containing an error frame ([`StateChangeError::ErrorMessage`]) is
+    /// fatal and ends the returned stream as usual, since it doesn't indicate the connection was
+    /// lost. Exhausting `policy.max_retries` is likewise fatal.
+    pub fn resilient<F>(
+        self,
+        policy: ReconnectPolicy,
+        mut reconnect: impl FnMut(IdleSubscription) -> F + Send + 'static,
+    ) -> StateChanges
+    where
+        F: Future<Output = Result<StateChanges, MpdProtocolError>> + Send + 'static,
+    {
+        let subscription = self.subscription.clone();
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            let mut current = self;
+
+            loop {
+                match poll_fn(|cx| Pin::new(&mut current).poll_next(cx)).await {
+                    Some(Ok(subsystem)) => {
+                        if tx.send(Ok(subsystem)).is_err() {
+                            return;
+                        }
+                    }
+                    Some(Err(StateChangeError::Protocol(MpdProtocolError::Io(_)))) | None => {
+                        match reconnect_with_backoff(
+                            &policy,
+                            &mut reconnect,
+                            &current.subscription,
+                        )
+                        .await
+                        {
+                            Some(reconnected) => {
+                                current = reconnected;
+
+                                if tx.send(Ok(resync_event())).is_err() {
+                                    return;
+                                }
+                            }
+                            None => return,
+                        }
+                    }
+                    Some(Err(e)) => {
+                        let _ = tx.send(Err(e));
+                        return;
+                    }
+                }
+            }
+        });
+
+        StateChanges { rx, subscription }
+    }
+}
+
+/// Repeatedly calls `reconnect` with exponential backoff per `policy` until it succeeds or
+/// `policy.max_retries` is exhausted, in which case `None` is returned.
+async fn reconnect_with_backoff<F>(
+    policy: &ReconnectPolicy,
+    reconnect: &mut (impl FnMut(IdleSubscription) -> F + Send),
+    subscription: &IdleSubscription,
+) -> Option<StateChanges>
+where
+    F: Future<Output = Result<StateChanges, MpdProtocolError>>,
+{
+    let mut attempt = 0;
+    let mut backoff = policy.initial_backoff;
+
+    loop {
+        if let Some(max) = policy.max_retries {
+            if attempt >= max {
+                return None;
+            }
+        }
+
+        time::sleep(backoff).await;
+
+        match reconnect(subscription.clone()).await {
+            Ok(reconnected) => return Some(reconnected),
+            Err(_) => {
+                attempt += 1;
+                backoff = (backoff * 2).min(policy.max_backoff);
+            }
+        }
+    }
+}
+
+/// Synthetic event emitted by [`StateChanges::resilient`] right after a successful reconnect, to
+/// signal that consumers should re-fetch any state they're mirroring.
+fn resync_event() -> Subsystem {
+    Subsystem::Other(Box::from(RESYNC_SUBSYSTEM))
+}
+
+#[cfg(test)]
+mod resilient_tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use super::*;
+
+    fn unconnected() -> StateChanges {
+        let (_tx, rx) = mpsc::unbounded_channel();
+        StateChanges {
+            rx,
+            subscription: IdleSubscription::all(),
+        }
+    }
+
+    #[tokio::test]
+    async fn reconnects_when_stream_ends() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let changes = StateChanges {
+            rx,
+            subscription: IdleSubscription::all(),
+        };
+
+        // Simulate MPD closing the connection: the stream ends by yielding `None`, as documented
+        // on `StateChanges`, not by yielding an `Err` item.
+        drop(tx);
+
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let attempts_for_reconnect = Arc::clone(&attempts);
+
+        let mut resilient = changes.resilient(
+            ReconnectPolicy {
+                initial_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(1),
+                max_retries: Some(1),
+            },
+            move |_subscription| {
+                let attempts = Arc::clone(&attempts_for_reconnect);
+                async move {
+                    attempts.fetch_add(1, Ordering::SeqCst);
+                    Ok(unconnected())
+                }
+            },
+        );
+
+        let event = poll_fn(|cx| Pin::new(&mut resilient).poll_next(cx)).await;
+
+        assert!(matches!(event, Some(Ok(Subsystem::Other(_)))));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+}
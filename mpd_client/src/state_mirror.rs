@@ -0,0 +1,361 @@
+//! A local mirror of frequently-polled MPD state, kept automatically in sync.
+//!
+//! This implements the common idiom of "receive a subsystem event, then issue `status` /
+//! `playlistinfo` and diff the result" once, so consumers can just read the latest snapshot
+//! instead of writing idle/status plumbing themselves.
+
+use std::{future::poll_fn, pin::Pin, sync::Arc};
+
+use futures_core::stream::Stream;
+use tokio::sync::watch;
+
+use crate::{
+    client::{CommandError, MpdCommands},
+    responses::{Output, Playlist, SongInQueue, Stats, Status},
+    state_changes::{StateChanges, Subsystem},
+};
+
+/// The subset of MPD state kept in sync by a [`StateMirror`].
+///
+/// Each field reflects the most recent successful fetch triggered by the [`Subsystem`] events
+/// that affect it, and starts out `None` until the first relevant event has been handled.
+#[derive(Clone, Debug, Default)]
+pub struct MirroredState {
+    /// Refreshed on [`Subsystem::Player`], [`Subsystem::Mixer`], [`Subsystem::Options`] and
+    /// [`Subsystem::Queue`].
+    pub status: Option<Arc<Status>>,
+    /// Refreshed on [`Subsystem::Queue`], alongside `status`.
+    pub queue: Option<Arc<Vec<SongInQueue>>>,
+    /// Refreshed on [`Subsystem::StoredPlaylist`].
+    pub playlists: Option<Arc<Vec<Playlist>>>,
+    /// Refreshed on [`Subsystem::Output`].
+    pub outputs: Option<Arc<Vec<Output>>>,
+    /// Refreshed on [`Subsystem::Database`] and [`Subsystem::Update`].
+    pub stats: Option<Arc<Stats>>,
+    /// The error from the most recent failed refresh attempt, if any.
+    ///
+    /// A failed refresh doesn't stop the mirror; the affected field(s) simply keep their last
+    /// known value until the next matching event triggers a successful refresh. This is surfaced
+    /// so consumers can notice and react (e.g. log or show a warning) instead of state silently
+    /// going stale forever.
+    pub last_error: Option<Arc<CommandError>>,
+}
+
+/// A cheaply-cloneable handle to locally mirrored MPD state.
+///
+/// Construct with [`StateMirror::spawn`], which starts a background task refreshing the relevant
+/// part of [`MirroredState`] whenever a matching [`Subsystem`] event is observed. Read the current
+/// snapshot at any time with [`StateMirror::status`], [`StateMirror::queue`] and so on, without
+/// ever touching `idle` or `status` directly.
+#[derive(Clone, Debug)]
+pub struct StateMirror {
+    state: watch::Receiver<MirroredState>,
+}
+
+impl StateMirror {
+    /// Spawns a background task which refreshes the mirrored state using `client` in response to
+    /// events from `changes`, and returns a handle for reading it.
+    ///
+    /// `client` only needs to implement [`MpdCommands`] (implemented by [`crate::client::Client`])
+    /// rather than being a concrete `Client`, so this can be driven by a test double.
+    ///
+    /// `changes` is consumed by the background task; its events are not passed on to any other
+    /// part of the application.
+    ///
+    /// The task keeps running, reconnect-or-not, until `changes` ends or yields an error, or until
+    /// every [`StateMirror`] handle (including the one returned here) has been dropped, which it
+    /// notices after handling the next event; a failed refresh is recorded in
+    /// [`MirroredState::last_error`] rather than stopping the mirror.
+    pub fn spawn<C>(client: C, mut changes: StateChanges) -> Self
+    where
+        C: MpdCommands + Send + Sync + 'static,
+    {
+        let (tx, rx) = watch::channel(MirroredState::default());
+
+        tokio::spawn(async move {
+            loop {
+                if tx.is_closed() {
+                    return;
+                }
+
+                let subsystem = match poll_fn(|cx| Pin::new(&mut changes).poll_next(cx)).await {
+                    Some(Ok(subsystem)) => subsystem,
+                    Some(Err(_)) | None => return,
+                };
+
+                if let Err(error) = Self::refresh(&client, &tx, &subsystem).await {
+                    tx.send_modify(|state| state.last_error = Some(Arc::new(error)));
+                }
+            }
+        });
+
+        Self { state: rx }
+    }
+
+    /// Returns the most recently mirrored `status`, if any has been fetched yet.
+    pub fn status(&self) -> Option<Arc<Status>> {
+        self.state.borrow().status.clone()
+    }
+
+    /// Returns the most recently mirrored queue, if any has been fetched yet.
+    pub fn queue(&self) -> Option<Arc<Vec<SongInQueue>>> {
+        self.state.borrow().queue.clone()
+    }
+
+    /// Returns the most recently mirrored stored playlists, if any have been fetched yet.
+    pub fn playlists(&self) -> Option<Arc<Vec<Playlist>>> {
+        self.state.borrow().playlists.clone()
+    }
+
+    /// Returns the most recently mirrored outputs, if any have been fetched yet.
+    pub fn outputs(&self) -> Option<Arc<Vec<Output>>> {
+        self.state.borrow().outputs.clone()
+    }
+
+    /// Returns the most recently mirrored `stats`, if any have been fetched yet.
+    pub fn stats(&self) -> Option<Arc<Stats>> {
+        self.state.borrow().stats.clone()
+    }
+
+    /// Returns the error from the most recent failed refresh attempt, if any.
+    pub fn last_error(&self) -> Option<Arc<CommandError>> {
+        self.state.borrow().last_error.clone()
+    }
+
+    /// Fetches and stores whatever state is affected by `subsystem`.
+    ///
+    /// The resync event emitted by [`crate::state_changes::StateChanges::resilient`] after a
+    /// reconnect is treated as affecting everything, since changes may have been missed while
+    /// disconnected.
+    async fn refresh<C: MpdCommands>(
+        client: &C,
+        tx: &watch::Sender<MirroredState>,
+        subsystem: &Subsystem,
+    ) -> Result<(), CommandError> {
+        if subsystem.is_resync() {
+            return Self::refresh_all(client, tx).await;
+        }
+
+        match subsystem {
+            Subsystem::Player | Subsystem::Mixer | Subsystem::Options => {
+                let status = client.status().await?;
+                tx.send_modify(|state| state.status = Some(Arc::new(status)));
+            }
+            Subsystem::Queue => {
+                let status = client.status().await?;
+                let queue = client.queue().await?;
+                tx.send_modify(|state| {
+                    state.status = Some(Arc::new(status));
+                    state.queue = Some(Arc::new(queue));
+                });
+            }
+            Subsystem::StoredPlaylist => {
+                let playlists = client.playlists().await?;
+                tx.send_modify(|state| state.playlists = Some(Arc::new(playlists)));
+            }
+            Subsystem::Output => {
+                let outputs = client.outputs().await?;
+                tx.send_modify(|state| state.outputs = Some(Arc::new(outputs)));
+            }
+            Subsystem::Database | Subsystem::Update => {
+                let stats = client.stats().await?;
+                tx.send_modify(|state| state.stats = Some(Arc::new(stats)));
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Refetches every piece of mirrored state, used after a resync event.
+    async fn refresh_all<C: MpdCommands>(
+        client: &C,
+        tx: &watch::Sender<MirroredState>,
+    ) -> Result<(), CommandError> {
+        let status = client.status().await?;
+        let queue = client.queue().await?;
+        let playlists = client.playlists().await?;
+        let outputs = client.outputs().await?;
+        let stats = client.stats().await?;
+
+        tx.send_modify(|state| {
+            state.status = Some(Arc::new(status));
+            state.queue = Some(Arc::new(queue));
+            state.playlists = Some(Arc::new(playlists));
+            state.outputs = Some(Arc::new(outputs));
+            state.stats = Some(Arc::new(stats));
+        });
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::state_changes::IdleSubscription;
+
+    /// A test double for [`MpdCommands`] that counts calls per command and can be made to fail
+    /// its next `status` call once.
+    #[derive(Default)]
+    struct FakeClient {
+        status_calls: AtomicUsize,
+        queue_calls: AtomicUsize,
+        playlists_calls: AtomicUsize,
+        outputs_calls: AtomicUsize,
+        stats_calls: AtomicUsize,
+        fail_next_status: std::sync::atomic::AtomicBool,
+    }
+
+    impl MpdCommands for FakeClient {
+        async fn status(&self) -> Result<Status, CommandError> {
+            self.status_calls.fetch_add(1, Ordering::SeqCst);
+
+            if self.fail_next_status.swap(false, Ordering::SeqCst) {
+                return Err(CommandError::Protocol(mpd_protocol::MpdProtocolError::Io(
+                    std::io::Error::other("simulated failure"),
+                )));
+            }
+
+            Ok(Status::default())
+        }
+
+        async fn queue(&self) -> Result<Vec<SongInQueue>, CommandError> {
+            self.queue_calls.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![SongInQueue::default()])
+        }
+
+        async fn playlists(&self) -> Result<Vec<Playlist>, CommandError> {
+            self.playlists_calls.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![Playlist::default()])
+        }
+
+        async fn outputs(&self) -> Result<Vec<Output>, CommandError> {
+            self.outputs_calls.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![Output::default()])
+        }
+
+        async fn stats(&self) -> Result<Stats, CommandError> {
+            self.stats_calls.fetch_add(1, Ordering::SeqCst);
+            Ok(Stats::default())
+        }
+    }
+
+    fn unbounded_changes() -> (
+        mpsc::UnboundedSender<Result<Subsystem, crate::state_changes::StateChangeError>>,
+        StateChanges,
+    ) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        (
+            tx,
+            StateChanges {
+                rx,
+                subscription: IdleSubscription::all(),
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn refresh_only_touches_fields_mapped_to_the_subsystem() {
+        let client = FakeClient::default();
+        let (tx, _rx) = watch::channel(MirroredState::default());
+
+        StateMirror::refresh(&client, &tx, &Subsystem::StoredPlaylist)
+            .await
+            .unwrap();
+
+        assert_eq!(client.playlists_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(client.status_calls.load(Ordering::SeqCst), 0);
+        assert_eq!(client.stats_calls.load(Ordering::SeqCst), 0);
+        assert_eq!(client.outputs_calls.load(Ordering::SeqCst), 0);
+
+        let state = tx.borrow();
+        assert!(state.playlists.is_some());
+        assert!(state.status.is_none());
+    }
+
+    #[tokio::test]
+    async fn queue_event_refreshes_status_alongside_the_queue() {
+        let client = FakeClient::default();
+        let (tx, _rx) = watch::channel(MirroredState::default());
+
+        StateMirror::refresh(&client, &tx, &Subsystem::Queue)
+            .await
+            .unwrap();
+
+        assert_eq!(client.status_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(client.queue_calls.load(Ordering::SeqCst), 1);
+
+        let state = tx.borrow();
+        assert!(state.status.is_some());
+        assert!(state.queue.is_some());
+    }
+
+    #[tokio::test]
+    async fn resync_event_refreshes_everything() {
+        let client = FakeClient::default();
+        let (tx, _rx) = watch::channel(MirroredState::default());
+
+        let resync = Subsystem::Other(Box::from("resync"));
+        assert!(resync.is_resync());
+
+        StateMirror::refresh(&client, &tx, &resync).await.unwrap();
+
+        assert_eq!(client.status_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(client.queue_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(client.playlists_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(client.outputs_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(client.stats_calls.load(Ordering::SeqCst), 1);
+
+        let state = tx.borrow();
+        assert!(state.status.is_some());
+        assert!(state.queue.is_some());
+        assert!(state.playlists.is_some());
+        assert!(state.outputs.is_some());
+        assert!(state.stats.is_some());
+    }
+
+    #[tokio::test]
+    async fn failed_refresh_is_recorded_without_stopping_the_mirror() {
+        let client = Arc::new(FakeClient::default());
+        client.fail_next_status.store(true, Ordering::SeqCst);
+
+        let (tx, changes) = unbounded_changes();
+        let mirror = StateMirror::spawn(Arc::clone(&client), changes);
+
+        tx.send(Ok(Subsystem::Player)).unwrap();
+
+        while mirror.last_error().is_none() {
+            tokio::task::yield_now().await;
+        }
+
+        assert!(mirror.status().is_none());
+
+        tx.send(Ok(Subsystem::Player)).unwrap();
+
+        while mirror.status().is_none() {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn spawn_stops_once_every_handle_is_dropped() {
+        let client = Arc::new(FakeClient::default());
+        let (tx, changes) = unbounded_changes();
+        let mirror = StateMirror::spawn(Arc::clone(&client), changes);
+
+        drop(mirror);
+
+        // The background task notices on its next wake-up and exits, dropping `changes` instead of
+        // polling it and calling the client forever.
+        assert!(tx.send(Ok(Subsystem::Player)).is_ok());
+
+        while !tx.is_closed() {
+            tokio::task::yield_now().await;
+        }
+    }
+}